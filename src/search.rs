@@ -1,105 +1,336 @@
 use itertools::Itertools;
+use regex::RegexBuilder;
 use strsim::damerau_levenshtein;
 
 use crate::{
-    nodes::word::{Word, WordType},
+    nodes::{
+        root::{Component, ComponentRoot},
+        textcomponent::TextNode,
+        word::{Word, WordType},
+    },
     pages::file_explorer::{FileTree, MdFile},
     util::colors::COLOR_CONFIG,
 };
 
-pub fn find_md_files() -> FileTree {
-    let mut ignored_files = Vec::new();
+/// Selects which algorithm the search functions in this module use to match a
+/// query against text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Approximate, whole-window matching using Damerau-Levenshtein distance.
+    Fuzzy { precision: usize },
+    /// A regular expression, compiled with the `regex` crate.
+    Regex,
+    /// An exact substring match, cased according to [`MatchOptions::case`].
+    Literal,
+}
 
-    if COLOR_CONFIG.gitignore {
-        let gitignore = std::fs::read_to_string(".gitignore");
-        if let Ok(gitignore) = gitignore {
-            for line in gitignore.lines() {
-                if line.starts_with('#') || line.is_empty() {
-                    continue;
-                }
-                ignored_files.push(line.to_string());
-            }
+impl Default for SearchMode {
+    fn default() -> Self {
+        SearchMode::Fuzzy { precision: 0 }
+    }
+}
+
+/// Overrides the case sensitivity a search uses instead of the implicit
+/// smart-case rule ("any uppercase char in the query makes it case
+/// sensitive").
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Case sensitive only if `query` contains an uppercase character.
+    Smart,
+    Sensitive,
+    Insensitive,
+}
+
+/// Extra knobs for the search functions in this module, alongside
+/// [`SearchMode`]: a [`Case`] override for smart-case, and whether a match
+/// must be bounded by non-alphanumeric characters (or text edges) to count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchOptions {
+    pub case: Case,
+    pub whole_word: bool,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        MatchOptions {
+            case: Case::Smart,
+            whole_word: false,
         }
     }
+}
+
+fn is_case_sensitive(query: &str, case: Case) -> bool {
+    match case {
+        Case::Smart => query.chars().any(|c| c.is_uppercase()),
+        Case::Sensitive => true,
+        Case::Insensitive => false,
+    }
+}
 
+pub fn find_md_files() -> FileTree {
     let mut tree = FileTree::new();
-    let mut stack = vec![std::path::PathBuf::from(".")];
-    while let Some(path) = stack.pop() {
-        for entry in if let Ok(entries) = std::fs::read_dir(&path) {
-            entries
-        } else {
-            continue;
-        } {
-            let path = if let Ok(path) = entry {
-                path.path()
-            } else {
+    let mut ignore_stack = gitignore::GitignoreStack::new();
+    walk_dir(
+        std::path::Path::new("."),
+        0,
+        COLOR_CONFIG.gitignore,
+        &mut ignore_stack,
+        &mut tree,
+    );
+    tree.sort_2();
+    tree
+}
+
+/// Recursively walks `dir`, adding every `.md` file to `tree`. As the walk
+/// descends into a directory, that directory's `.gitignore` (if any) is
+/// pushed onto `ignore_stack`, so patterns from more deeply nested
+/// `.gitignore` files are consulted last (and therefore win, per git's own
+/// precedence rules) and are popped back off once the directory is done.
+fn walk_dir(
+    dir: &std::path::Path,
+    depth: usize,
+    gitignore_enabled: bool,
+    ignore_stack: &mut gitignore::GitignoreStack,
+    tree: &mut FileTree,
+) {
+    if gitignore_enabled {
+        ignore_stack.push_dir(dir, depth);
+    }
+
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(path_str) = path.to_str() else {
                 continue;
             };
-            if path.is_dir() {
-                stack.push(path);
-            } else if path.extension().unwrap_or_default() == "md" {
-                let (path_str, path_name) =
-                    if let (Some(path_str), Some(path_name)) = (path.to_str(), path.file_name()) {
-                        (path_str, path_name.to_str().unwrap_or("UNKNOWN"))
-                    } else {
-                        continue;
-                    };
-                // Check if the file is in the ignored files list
-                if ignored_files
-                    .iter()
-                    .any(|ignored_file| !find(ignored_file, path_str, 0).is_empty())
-                {
+            let is_dir = path.is_dir();
+
+            if gitignore_enabled {
+                let rel_path = path_str.trim_start_matches("./");
+                if ignore_stack.is_ignored(rel_path, is_dir) {
                     continue;
                 }
+            }
 
+            if is_dir {
+                walk_dir(&path, depth + 1, gitignore_enabled, ignore_stack, tree);
+            } else if path.extension().unwrap_or_default() == "md" {
+                let Some(path_name) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
                 tree.add_file(MdFile::new(path_str.to_string(), path_name.to_string()));
             }
         }
     }
-    tree.sort_2();
-    tree
+
+    if gitignore_enabled {
+        ignore_stack.pop_dir();
+    }
 }
 
+/// Ranks `files` against `query` the way fzf ranks paths: each file keeps its
+/// best fzf-style subsequence score, and the result is sorted from the
+/// highest score down. Files where `query` isn't a subsequence of the path
+/// are dropped.
 pub fn find_files(files: &[MdFile], query: &str) -> Vec<MdFile> {
     if query.is_empty() {
         return files.to_vec();
     }
 
-    // Check if any char in the query is uppercase, making the search case sensitive
+    let mut scored: Vec<(MdFile, i64)> = files
+        .iter()
+        .filter_map(|file| fuzzy_score(&file.path, query).map(|score| (file.clone(), score)))
+        .collect();
+
+    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.into_iter().map(|(file, _)| file).collect()
+}
+
+const FZF_SCORE_MATCH: i64 = 16;
+const FZF_SCORE_GAP_PENALTY: i64 = -3;
+const FZF_SCORE_CONSECUTIVE_BONUS: i64 = 8;
+const FZF_SCORE_WORD_START_BONUS: i64 = 24;
+
+/// Scores `path` against `query` fzf-style via a small dynamic-programming
+/// alignment over (query index, path index): `row[j]` holds the best score
+/// (and the length of the consecutive run it ends on) for matching the
+/// query characters seen so far such that the last one lands exactly on
+/// `path` position `j`, considering every earlier position the previous
+/// character could have matched rather than greedily taking the first one.
+/// Matches that start a "word" (right after a path separator, `.`, `-`, `_`,
+/// or at a camelCase boundary) and consecutive runs of matches are rewarded;
+/// gaps between matches are penalized. Honors the same smart-case rule as
+/// the rest of this module. Returns `None` if `query` is not a subsequence
+/// of `path`.
+fn fuzzy_score(path: &str, query: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
     let case_sensitive = query.chars().any(|c| c.is_uppercase());
+    let haystack: Vec<char> = path.chars().collect();
+    let needle: Vec<char> = query.chars().collect();
 
-    files
-        .iter()
-        .filter(|file| {
-            let file_path = if case_sensitive {
-                file.path.to_owned()
-            } else {
-                file.path.to_lowercase()
-            };
-            let res = char_windows(&file_path, query.len())
-                .any(|window| damerau_levenshtein(window, query) == 0);
-            res
-        })
-        .cloned()
-        .collect()
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    let matches_char = |h: char, n: char| {
+        if case_sensitive {
+            h == n
+        } else {
+            h.to_ascii_lowercase() == n.to_ascii_lowercase()
+        }
+    };
+
+    let is_word_start = |j: usize| {
+        j == 0
+            || matches!(haystack[j - 1], '/' | '.' | '-' | '_')
+            || (haystack[j - 1].is_lowercase() && haystack[j].is_uppercase())
+    };
+
+    let match_bonus = |j: usize| {
+        let mut bonus = FZF_SCORE_MATCH;
+        if is_word_start(j) {
+            bonus += FZF_SCORE_WORD_START_BONUS;
+        }
+        bonus
+    };
+
+    let n = haystack.len();
+
+    // `row[j]` is `Some((score, run))` when the needle character for the
+    // current row can land on `haystack[j]`: `score` is the best total for
+    // that alignment, `run` the length of the consecutive match run it ends.
+    let mut row: Vec<Option<(i64, i64)>> = (0..n)
+        .map(|j| matches_char(haystack[j], needle[0]).then(|| (match_bonus(j), 0)))
+        .collect();
+
+    for (i, &needle_char) in needle.iter().enumerate().skip(1) {
+        let prev_row = row;
+        row = vec![None; n];
+
+        for j in i..n {
+            if !matches_char(haystack[j], needle_char) {
+                continue;
+            }
+
+            let mut best: Option<(i64, i64)> = None;
+            for (k, &prev_cell) in prev_row.iter().enumerate().take(j).skip(i - 1) {
+                let Some((prev_score, prev_run)) = prev_cell else {
+                    continue;
+                };
+
+                let candidate = if k == j - 1 {
+                    let run = prev_run + 1;
+                    (prev_score + FZF_SCORE_CONSECUTIVE_BONUS * run, run)
+                } else {
+                    (prev_score + FZF_SCORE_GAP_PENALTY * (j - k - 1) as i64, 0)
+                };
+
+                if best.map_or(true, |(best_score, _)| candidate.0 > best_score) {
+                    best = Some(candidate);
+                }
+            }
+
+            row[j] = best.map(|(score, run)| (score + match_bonus(j), run));
+        }
+    }
+
+    row.into_iter()
+        .filter_map(|cell| cell.map(|(score, _)| score))
+        .max()
 }
 
-pub fn find_with_backoff(query: &str, text: &str) -> Vec<usize> {
-    let precision = 0;
-    let mut result = find(query, text, precision);
+pub fn find_with_backoff(
+    query: &str,
+    text: &str,
+    mode: SearchMode,
+    options: MatchOptions,
+) -> Vec<usize> {
+    let mut result = find(query, text, mode, options);
     if result.is_empty() {
-        let precision = 1;
-        result = find(query, text, precision);
+        if let SearchMode::Fuzzy { precision } = mode {
+            result = find(
+                query,
+                text,
+                SearchMode::Fuzzy {
+                    precision: precision + 1,
+                },
+                options,
+            );
+        }
     }
     result
 }
 
-pub fn find(query: &str, text: &str, precision: usize) -> Vec<usize> {
+/// Returns the char offset that each match of `query` in `text` starts at,
+/// according to `mode` and `options`.
+pub fn find(query: &str, text: &str, mode: SearchMode, options: MatchOptions) -> Vec<usize> {
+    find_ranges(query, text, mode, options)
+        .into_iter()
+        .map(|(start, _)| start)
+        .collect()
+}
+
+/// Returns the `[start, end)` char range of each match of `query` in `text`,
+/// according to `mode` and `options`. `Regex` and `Literal` matches never
+/// overlap; `Fuzzy` reports every window within `precision`, and those may
+/// overlap.
+fn find_ranges(
+    query: &str,
+    text: &str,
+    mode: SearchMode,
+    options: MatchOptions,
+) -> Vec<(usize, usize)> {
+    let case_sensitive = is_case_sensitive(query, options.case);
+    let ranges = match mode {
+        SearchMode::Fuzzy { precision } => {
+            find_ranges_fuzzy(query, text, precision, case_sensitive)
+        }
+        SearchMode::Literal => find_ranges_literal(query, text, case_sensitive),
+        SearchMode::Regex => find_ranges_regex(query, text, case_sensitive),
+    };
+
+    if options.whole_word {
+        let chars: Vec<char> = text.chars().collect();
+        ranges
+            .into_iter()
+            .filter(|&(start, end)| is_whole_word(&chars, start, end))
+            .collect()
+    } else {
+        ranges
+    }
+}
+
+/// Returns whether the match spanning `[start, end)` in `chars` is bounded by
+/// non-alphanumeric characters (or the edges of `chars`) on both sides.
+fn is_whole_word(chars: &[char], start: usize, end: usize) -> bool {
+    let before_ok = start == 0 || !chars[start - 1].is_alphanumeric();
+    let after_ok = end >= chars.len() || !chars[end].is_alphanumeric();
+    before_ok && after_ok
+}
+
+fn find_ranges_fuzzy(
+    query: &str,
+    text: &str,
+    precision: usize,
+    case_sensitive: bool,
+) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
     let mut result = Vec::new();
 
-    let case_sensitive = query.chars().any(|c| c.is_uppercase());
+    let query_len = query.chars().count();
+    let query = if case_sensitive {
+        query.to_owned()
+    } else {
+        query.to_lowercase()
+    };
 
-    char_windows(text, query.len())
+    char_windows(text, query_len)
         .enumerate()
         .for_each(|(i, window)| {
             let window = if case_sensitive {
@@ -107,21 +338,71 @@ pub fn find(query: &str, text: &str, precision: usize) -> Vec<usize> {
             } else {
                 window.to_lowercase()
             };
-            let score = damerau_levenshtein(query, &window);
+            let score = damerau_levenshtein(&query, &window);
             if score <= precision {
-                result.push(i);
+                result.push((i, i + query_len));
             }
         });
 
     result
 }
 
-/// Returns line numbers that match the query with the given precision.
-pub fn line_match(query: &str, text: Vec<&str>, precision: usize) -> Vec<usize> {
+fn find_ranges_literal(query: &str, text: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let query_len = query.chars().count();
+    let (haystack, needle) = if case_sensitive {
+        (text.to_owned(), query.to_owned())
+    } else {
+        (text.to_lowercase(), query.to_lowercase())
+    };
+
+    haystack
+        .match_indices(&needle)
+        .map(|(byte_idx, _)| {
+            let start = haystack[..byte_idx].chars().count();
+            (start, start + query_len)
+        })
+        .collect()
+}
+
+fn find_ranges_regex(query: &str, text: &str, case_sensitive: bool) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let regex = match RegexBuilder::new(query)
+        .case_insensitive(!case_sensitive)
+        .build()
+    {
+        Ok(regex) => regex,
+        Err(_) => return Vec::new(),
+    };
+
+    regex
+        .find_iter(text)
+        .map(|m| {
+            let start = text[..m.start()].chars().count();
+            let end = text[..m.end()].chars().count();
+            (start, end)
+        })
+        .collect()
+}
+
+/// Returns line numbers that match the query with the given search mode and
+/// options.
+pub fn line_match(
+    query: &str,
+    text: Vec<&str>,
+    mode: SearchMode,
+    options: MatchOptions,
+) -> Vec<usize> {
     text.iter()
         .enumerate()
         .filter_map(|(i, line)| {
-            if find(query, line, precision).is_empty() {
+            if find(query, line, mode, options).is_empty() {
                 None
             } else {
                 Some(i)
@@ -133,20 +414,39 @@ pub fn line_match(query: &str, text: Vec<&str>, precision: usize) -> Vec<usize>
 pub fn line_match_and_index(
     query: &str,
     lines: Vec<&str>,
-    precision: usize,
+    mode: SearchMode,
+    options: MatchOptions,
 ) -> Vec<(usize, usize)> {
     lines
         .iter()
         .enumerate()
         .flat_map(|(i, line)| {
-            find(query, line, precision)
+            find(query, line, mode, options)
                 .into_iter()
                 .map(move |j| (i, j))
         })
         .collect()
 }
 
-pub fn find_with_ref<'a>(query: &str, text: Vec<&'a Word>) -> Vec<&'a Word> {
+pub fn find_with_ref<'a>(
+    query: &str,
+    text: Vec<&'a Word>,
+    mode: SearchMode,
+    options: MatchOptions,
+) -> Vec<&'a Word> {
+    match mode {
+        SearchMode::Fuzzy { .. } => find_with_ref_fuzzy(query, text, options),
+        SearchMode::Regex | SearchMode::Literal => {
+            find_with_ref_spanned(query, text, mode, options)
+        }
+    }
+}
+
+fn find_with_ref_fuzzy<'a>(
+    query: &str,
+    text: Vec<&'a Word>,
+    options: MatchOptions,
+) -> Vec<&'a Word> {
     let window_size = query
         .split_whitespace()
         .fold(0usize, |acc, _| acc + 2)
@@ -156,10 +456,24 @@ pub fn find_with_ref<'a>(query: &str, text: Vec<&'a Word>) -> Vec<&'a Word> {
         return Vec::new();
     }
 
+    let case_sensitive = is_case_sensitive(query, options.case);
+    let query = if case_sensitive {
+        query.to_owned()
+    } else {
+        query.to_lowercase()
+    };
+    let chars: Vec<char> = text
+        .iter()
+        .map(|word| word.content())
+        .join("")
+        .chars()
+        .collect();
+    let spans = word_spans(text.iter().map(|word| word.content()));
+
     text.windows(window_size)
-        .filter(|word| {
-            let mut words = word.iter().map(|c| c.content()).join("");
-            let case_sensitive = query.chars().any(|c| c.is_uppercase());
+        .enumerate()
+        .filter(|(i, window)| {
+            let mut words = window.iter().map(|c| c.content()).join("");
 
             words = if case_sensitive {
                 words.to_owned()
@@ -167,14 +481,57 @@ pub fn find_with_ref<'a>(query: &str, text: Vec<&'a Word>) -> Vec<&'a Word> {
                 words.to_lowercase()
             };
 
-            damerau_levenshtein(query, &words) == 0
+            if damerau_levenshtein(&query, &words) != 0 {
+                return false;
+            }
+
+            if options.whole_word {
+                let (start, _) = spans[*i];
+                let (_, end) = spans[*i + window_size - 1];
+                is_whole_word(&chars, start, end)
+            } else {
+                true
+            }
         })
-        .flatten()
-        .copied()
+        .flat_map(|(_, window)| window.iter().copied())
         .collect::<Vec<_>>()
 }
 
-pub fn find_and_mark<'a>(query: &str, text: &'a mut Vec<&'a mut Word>) {
+/// Joins the words of a component into a single string, matches `query`
+/// against it according to `mode` and `options`, and returns every `Word`
+/// whose content overlaps a match.
+fn find_with_ref_spanned<'a>(
+    query: &str,
+    text: Vec<&'a Word>,
+    mode: SearchMode,
+    options: MatchOptions,
+) -> Vec<&'a Word> {
+    let joined = text.iter().map(|word| word.content()).join("");
+    let spans = word_spans(text.iter().map(|word| word.content()));
+    let ranges = find_ranges(query, &joined, mode, options);
+
+    text.into_iter()
+        .zip(spans)
+        .filter(|(_, (start, end))| ranges.iter().any(|(rs, re)| start < re && end > rs))
+        .map(|(word, _)| word)
+        .collect()
+}
+
+pub fn find_and_mark<'a>(
+    query: &str,
+    text: &'a mut Vec<&'a mut Word>,
+    mode: SearchMode,
+    options: MatchOptions,
+) {
+    match mode {
+        SearchMode::Fuzzy { .. } => find_and_mark_fuzzy(query, text, options),
+        SearchMode::Regex | SearchMode::Literal => {
+            find_and_mark_spanned(query, text, mode, options)
+        }
+    }
+}
+
+fn find_and_mark_fuzzy(query: &str, text: &mut [&mut Word], options: MatchOptions) {
     let window_size = query
         .split_whitespace()
         .fold(0usize, |acc, _| acc + 2)
@@ -184,9 +541,22 @@ pub fn find_and_mark<'a>(query: &str, text: &'a mut Vec<&'a mut Word>) {
         return;
     }
 
-    windows_mut_for_each(text.as_mut_slice(), window_size, |window| {
+    let case_sensitive = is_case_sensitive(query, options.case);
+    let query = if case_sensitive {
+        query.to_owned()
+    } else {
+        query.to_lowercase()
+    };
+    let chars: Vec<char> = text
+        .iter()
+        .map(|word| word.content())
+        .join("")
+        .chars()
+        .collect();
+    let spans = word_spans(text.iter().map(|word| word.content()));
+
+    windows_mut_for_each(text, window_size, |start, window| {
         let mut words = window.iter().map(|c| c.content()).join("");
-        let case_sensitive = query.chars().any(|c| c.is_uppercase());
 
         words = if case_sensitive {
             words.to_owned()
@@ -194,19 +564,62 @@ pub fn find_and_mark<'a>(query: &str, text: &'a mut Vec<&'a mut Word>) {
             words.to_lowercase()
         };
 
-        if damerau_levenshtein(query, &words) == 0 {
-            window
-                .iter_mut()
-                .for_each(|word| word.set_kind(WordType::Selected));
+        if damerau_levenshtein(&query, &words) != 0 {
+            return;
+        }
+
+        if options.whole_word {
+            let (word_start, _) = spans[start];
+            let (_, word_end) = spans[start + window_size - 1];
+            if !is_whole_word(&chars, word_start, word_end) {
+                return;
+            }
         }
+
+        window
+            .iter_mut()
+            .for_each(|word| word.set_kind(WordType::Selected));
     })
 }
 
-fn windows_mut_for_each<T>(v: &mut [T], n: usize, f: impl Fn(&mut [T])) {
+/// Joins the words of a component into a single string, matches `query`
+/// against it according to `mode` and `options`, and marks every `Word`
+/// whose content overlaps a match as `WordType::Selected`.
+fn find_and_mark_spanned(
+    query: &str,
+    text: &mut [&mut Word],
+    mode: SearchMode,
+    options: MatchOptions,
+) {
+    let joined = text.iter().map(|word| word.content()).join("");
+    let spans = word_spans(text.iter().map(|word| word.content()));
+    let ranges = find_ranges(query, &joined, mode, options);
+
+    text.iter_mut().zip(spans).for_each(|(word, (start, end))| {
+        if ranges.iter().any(|(rs, re)| start < *re && end > *rs) {
+            word.set_kind(WordType::Selected);
+        }
+    });
+}
+
+/// Returns the `[start, end)` char range that each item in `contents` spans
+/// once they are all joined together in order.
+fn word_spans<'a>(contents: impl Iterator<Item = &'a str>) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for content in contents {
+        let len = content.chars().count();
+        spans.push((offset, offset + len));
+        offset += len;
+    }
+    spans
+}
+
+fn windows_mut_for_each<T>(v: &mut [T], n: usize, f: impl Fn(usize, &mut [T])) {
     let mut start = 0;
     let mut end = n;
     while end <= v.len() {
-        f(&mut v[start..end]);
+        f(start, &mut v[start..end]);
         start += 1;
         end += 1;
     }
@@ -222,19 +635,298 @@ fn char_windows(src: &str, win_size: usize) -> impl Iterator<Item = &'_ str> {
 }
 
 pub fn compare_heading(link_header: &str, header: &[Vec<Word>]) -> bool {
-    let header: String = header
-        .iter()
-        .flatten()
-        .map(|word| word.content().to_lowercase())
+    let header = slugify_heading(header.iter().flatten().map(|word| word.content()));
+    link_header == header
+}
+
+fn slugify_heading<'a>(words: impl Iterator<Item = &'a str>) -> String {
+    words
+        .map(|word| word.to_lowercase())
         .join("-")
         .trim_start_matches('-')
         .chars()
         .filter(|c| c.is_alphanumeric() || *c == '-')
         .dedup_by(|a, b| *a == '-' && *b == '-')
         .skip_while(|c| *c == '-')
-        .collect();
+        .collect()
+}
 
-    link_header == header
+/// One heading in a document's outline, mirroring an editor's
+/// document-symbol/outline feature: its nesting level, the GitHub-style
+/// anchor slug produced by the same rules as [`compare_heading`], its
+/// display text, and the index of the component it starts at within the
+/// document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadingOutline {
+    pub level: u8,
+    pub slug: String,
+    pub text: String,
+    pub component_index: usize,
+}
+
+/// Walks `root`'s components and collects every heading into an ordered
+/// outline.
+pub fn build_outline(root: &ComponentRoot) -> Vec<HeadingOutline> {
+    root.components()
+        .iter()
+        .enumerate()
+        .filter_map(|(component_index, component)| {
+            let Component::TextComponent(text_component) = component else {
+                return None;
+            };
+            let TextNode::Heading(level) = text_component.kind() else {
+                return None;
+            };
+
+            let text = text_component
+                .content()
+                .iter()
+                .map(|word| word.content())
+                .join("");
+            let slug = slugify_heading(text_component.content().iter().map(|word| word.content()));
+
+            Some(HeadingOutline {
+                level: *level,
+                slug,
+                text,
+                component_index,
+            })
+        })
+        .collect()
+}
+
+/// Returns the outline entry for the heading at or nearest above
+/// `component_index`, mirroring a "jump to previous section" command.
+pub fn previous_heading(
+    outline: &[HeadingOutline],
+    component_index: usize,
+) -> Option<&HeadingOutline> {
+    outline
+        .iter()
+        .rev()
+        .find(|heading| heading.component_index <= component_index)
+}
+
+/// Returns the outline entry for the next heading strictly after
+/// `component_index`, mirroring a "jump to next section" command.
+pub fn next_heading(outline: &[HeadingOutline], component_index: usize) -> Option<&HeadingOutline> {
+    outline
+        .iter()
+        .find(|heading| heading.component_index > component_index)
+}
+
+/// Resolves a `[text](#anchor)` link's anchor against `outline`, using the
+/// same slug rules as [`compare_heading`].
+pub fn resolve_heading_link<'a>(
+    outline: &'a [HeadingOutline],
+    anchor: &str,
+) -> Option<&'a HeadingOutline> {
+    outline.iter().find(|heading| heading.slug == anchor)
+}
+
+/// A minimal gitignore-pattern matcher used by [`walk_dir`] to decide which
+/// directories and files to skip, mirroring the precedence rules the `ignore`
+/// crate (and `git` itself) implement: rules from more deeply nested
+/// `.gitignore` files are consulted after, and therefore override, rules from
+/// their ancestors, and a later `!`-negated pattern re-includes a path an
+/// earlier pattern excluded.
+mod gitignore {
+    #[derive(Debug, Clone)]
+    struct Pattern {
+        glob: String,
+        negated: bool,
+        dir_only: bool,
+        anchored: bool,
+    }
+
+    impl Pattern {
+        fn parse(line: &str) -> Option<Pattern> {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+
+            let mut rest = line;
+            let negated = rest.starts_with('!');
+            if negated {
+                rest = &rest[1..];
+            }
+
+            let dir_only = rest.len() > 1 && rest.ends_with('/');
+            if dir_only {
+                rest = &rest[..rest.len() - 1];
+            }
+
+            // A slash anywhere but the end anchors the pattern to the
+            // directory its .gitignore lives in; no slash at all lets it
+            // match a path component at any depth.
+            let anchored = rest.contains('/');
+
+            Some(Pattern {
+                glob: rest.trim_start_matches('/').to_string(),
+                negated,
+                dir_only,
+                anchored,
+            })
+        }
+
+        fn matches(&self, local_path: &str, is_dir: bool) -> bool {
+            if self.dir_only && !is_dir {
+                return false;
+            }
+
+            if self.anchored {
+                glob_match(&self.glob, local_path)
+            } else {
+                let basename = local_path.rsplit('/').next().unwrap_or(local_path);
+                glob_match(&self.glob, basename)
+            }
+        }
+    }
+
+    /// A stack of rule-sets, one per directory the walk has descended into,
+    /// ordered least to most specific.
+    #[derive(Debug, Default)]
+    pub struct GitignoreStack {
+        levels: Vec<(usize, Vec<Pattern>)>,
+    }
+
+    impl GitignoreStack {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Reads `dir`'s `.gitignore` (if any) and pushes its rules onto the
+        /// stack. `depth` is `dir`'s distance, in path components, from the
+        /// walk's root, and is later used to compute each candidate path's
+        /// position relative to `dir`.
+        pub fn push_dir(&mut self, dir: &std::path::Path, depth: usize) {
+            let mut patterns = Vec::new();
+            if let Ok(contents) = std::fs::read_to_string(dir.join(".gitignore")) {
+                patterns.extend(contents.lines().filter_map(Pattern::parse));
+            }
+            self.levels.push((depth, patterns));
+        }
+
+        pub fn pop_dir(&mut self) {
+            self.levels.pop();
+        }
+
+        /// Returns whether `path` (relative to the walk's root, with `/`
+        /// separators) is ignored, applying every level's patterns in order
+        /// so the most specific `.gitignore` has the final say.
+        pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+            let components: Vec<&str> = path.split('/').collect();
+
+            let mut ignored = false;
+            for (depth, patterns) in &self.levels {
+                if *depth > components.len() {
+                    continue;
+                }
+                let local_path = components[*depth..].join("/");
+                for pattern in patterns {
+                    if pattern.matches(&local_path, is_dir) {
+                        ignored = !pattern.negated;
+                    }
+                }
+            }
+            ignored
+        }
+    }
+
+    /// A small glob matcher supporting `*` (no directory crossing), `**`
+    /// (zero or more path components) and `?`.
+    fn glob_match(pattern: &str, text: &str) -> bool {
+        glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+    }
+
+    fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some(b'*') if pattern.get(1) == Some(&b'*') => match pattern[2..].strip_prefix(b"/") {
+                // `**/` may only resume matching at the start of a component.
+                Some(rest) => (0..=text.len())
+                    .filter(|&i| i == 0 || text[i - 1] == b'/')
+                    .any(|i| glob_match_bytes(rest, &text[i..])),
+                None => (0..=text.len()).any(|i| glob_match_bytes(&pattern[2..], &text[i..])),
+            },
+            Some(b'*') => {
+                let rest = &pattern[1..];
+                (0..=text.len())
+                    .take_while(|&i| !text[..i].contains(&b'/'))
+                    .any(|i| glob_match_bytes(rest, &text[i..]))
+            }
+            Some(b'?') => {
+                !text.is_empty() && text[0] != b'/' && glob_match_bytes(&pattern[1..], &text[1..])
+            }
+            Some(&c) => {
+                !text.is_empty() && text[0] == c && glob_match_bytes(&pattern[1..], &text[1..])
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn stack_from(rules: &[(usize, &str)]) -> GitignoreStack {
+            let mut stack = GitignoreStack::new();
+            for (depth, contents) in rules {
+                let patterns = contents.lines().filter_map(Pattern::parse).collect();
+                stack.levels.push((*depth, patterns));
+            }
+            stack
+        }
+
+        #[test]
+        fn test_substring_pattern_is_not_ignored() {
+            let stack = stack_from(&[(0, "build")]);
+            assert!(!stack.is_ignored("my-build-notes.md", false));
+        }
+
+        #[test]
+        fn test_basename_pattern_matches_any_depth() {
+            let stack = stack_from(&[(0, "build")]);
+            assert!(stack.is_ignored("build", true));
+            assert!(stack.is_ignored("src/build", true));
+        }
+
+        #[test]
+        fn test_anchored_pattern_only_matches_at_root() {
+            let stack = stack_from(&[(0, "/docs")]);
+            assert!(stack.is_ignored("docs", true));
+            assert!(!stack.is_ignored("src/docs", true));
+        }
+
+        #[test]
+        fn test_dir_only_pattern_skips_files() {
+            let stack = stack_from(&[(0, "target/")]);
+            assert!(stack.is_ignored("target", true));
+            assert!(!stack.is_ignored("target", false));
+        }
+
+        #[test]
+        fn test_negation_reincludes_path() {
+            let stack = stack_from(&[(0, "*.md\n!keep.md")]);
+            assert!(stack.is_ignored("notes.md", false));
+            assert!(!stack.is_ignored("keep.md", false));
+        }
+
+        #[test]
+        fn test_more_specific_gitignore_wins() {
+            let stack = stack_from(&[(0, "*.md"), (1, "!keep.md")]);
+            assert!(!stack.is_ignored("src/keep.md", false));
+            assert!(stack.is_ignored("src/other.md", false));
+        }
+
+        #[test]
+        fn test_double_star_matches_nested_dirs() {
+            let stack = stack_from(&[(0, "/src/**/notes.md")]);
+            assert!(stack.is_ignored("src/notes.md", false));
+            assert!(stack.is_ignored("src/a/b/notes.md", false));
+            assert!(!stack.is_ignored("src/notes.txt", false));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -250,12 +942,29 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_find_empty_query_does_not_panic() {
+        let text = "Hello, world!";
+        for mode in [
+            SearchMode::Fuzzy { precision: 0 },
+            SearchMode::Regex,
+            SearchMode::Literal,
+        ] {
+            let result = find("", text, mode, MatchOptions::default());
+            assert_eq!(result, Vec::<usize>::new());
+        }
+    }
+
     #[test]
     fn test_find() {
         let text = "Hello, world!";
         let query = "world";
-        let precision = 0;
-        let result = find(query, text, precision);
+        let result = find(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![7]);
     }
 
@@ -263,7 +972,12 @@ mod tests {
     fn test_find_with_backoff() {
         let text = "Hello, world!";
         let query = "world";
-        let result = find_with_backoff(query, text);
+        let result = find_with_backoff(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![7]);
     }
 
@@ -271,7 +985,12 @@ mod tests {
     fn test_find_with_backoff_with_typo() {
         let text = "Hello, world!";
         let query = "wrold";
-        let result = find_with_backoff(query, text);
+        let result = find_with_backoff(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![7]);
     }
 
@@ -279,8 +998,12 @@ mod tests {
     fn test_vec_find() {
         let text = vec!["Hello", "hello", "world", "World"];
         let query = "world";
-        let precision = 0;
-        let result = line_match(query, text, precision);
+        let result = line_match(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![2, 3]);
     }
 
@@ -288,8 +1011,12 @@ mod tests {
     fn test_vec_find_less_precision() {
         let text = vec!["Hello", "hello", "world", "World"];
         let query = "world";
-        let precision = 1;
-        let result = line_match(query, text, precision);
+        let result = line_match(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 1 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![2, 3]);
     }
 
@@ -297,8 +1024,12 @@ mod tests {
     fn test_vec_find_with_typo() {
         let text = vec!["Hello", "hello", "world", "World"];
         let query = "wrold";
-        let precision = 2;
-        let result = line_match(query, text, precision);
+        let result = line_match(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 2 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![2, 3]);
     }
 
@@ -306,8 +1037,12 @@ mod tests {
     fn test_find_line_match_and_index() {
         let text = vec!["Hello", "hello", "world", "hello world"];
         let query = "world";
-        let precision = 0;
-        let result = line_match_and_index(query, text, precision);
+        let result = line_match_and_index(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![(2, 0), (3, 6)]);
     }
 
@@ -315,8 +1050,12 @@ mod tests {
     fn test_find_line_match_and_index_with_typo() {
         let text = vec!["Hello", "hello", "world", "hello world"];
         let query = "wrold";
-        let precision = 2;
-        let result = line_match_and_index(query, text, precision);
+        let result = line_match_and_index(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 2 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![(2, 0), (3, 6)]);
     }
 
@@ -324,11 +1063,98 @@ mod tests {
     fn test_find_line_match_and_index_with_leading_space() {
         let text = vec!["Hello", "hello", "world", " hello world"];
         let query = "world";
-        let precision = 0;
-        let result = line_match_and_index(query, text, precision);
+        let result = line_match_and_index(
+            query,
+            text,
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result, vec![(2, 0), (3, 7)]);
     }
 
+    #[test]
+    fn test_find_regex() {
+        let text = "Hello, world! hello, World!";
+        let query = r"[Ww]orld";
+        let result = find(query, text, SearchMode::Regex, MatchOptions::default());
+        assert_eq!(result, vec![7, 21]);
+    }
+
+    #[test]
+    fn test_find_regex_case_insensitive() {
+        let text = "Hello, world! hello, World!";
+        let query = "world";
+        let result = find(query, text, SearchMode::Regex, MatchOptions::default());
+        assert_eq!(result, vec![7, 21]);
+    }
+
+    #[test]
+    fn test_find_literal_is_smart_case() {
+        let text = "Hello, world! hello, World!";
+        let query = "World";
+        let result = find(query, text, SearchMode::Literal, MatchOptions::default());
+        assert_eq!(result, vec![21]);
+    }
+
+    #[test]
+    fn test_case_insensitive_overrides_smart_case() {
+        let text = "Hello, world! hello, World!";
+        let query = "World";
+        let options = MatchOptions {
+            case: Case::Insensitive,
+            whole_word: false,
+        };
+        let result = find(query, text, SearchMode::Literal, options);
+        assert_eq!(result, vec![7, 21]);
+    }
+
+    #[test]
+    fn test_case_sensitive_overrides_smart_case() {
+        let text = "TODO: todo later";
+        let query = "todo";
+        let options = MatchOptions {
+            case: Case::Sensitive,
+            whole_word: false,
+        };
+        let result = find(query, text, SearchMode::Literal, options);
+        assert_eq!(result, vec![6]);
+    }
+
+    #[test]
+    fn test_whole_word_excludes_partial_matches() {
+        let text = "terminal is in fashion";
+        let query = "in";
+        let options = MatchOptions {
+            case: Case::Smart,
+            whole_word: true,
+        };
+        let result = find(query, text, SearchMode::Literal, options);
+        assert_eq!(result, vec![12]);
+    }
+
+    #[test]
+    fn test_find_with_ref_fuzzy_whole_word_excludes_partial_matches() {
+        let text = vec![
+            Word::new("cat".to_string(), WordType::Normal),
+            Word::new("nap".to_string(), WordType::Normal),
+        ];
+
+        let componet = Component::TextComponent(TextComponent::new(TextNode::Paragraph, text));
+        let root = ComponentRoot::new(None, vec![componet]);
+        let options = MatchOptions {
+            case: Case::Smart,
+            whole_word: true,
+        };
+
+        let result = find_with_ref(
+            "cat",
+            root.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            options,
+        );
+        assert!(result.is_empty());
+    }
+
     #[test]
     fn test_word_by_ref() {
         let text = vec![
@@ -341,7 +1167,12 @@ mod tests {
         let componet = Component::TextComponent(TextComponent::new(TextNode::Paragraph, text));
         let root = ComponentRoot::new(None, vec![componet]);
         let query = "world";
-        let result = find_with_ref(query, root.words());
+        let result = find_with_ref(
+            query,
+            root.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result.len(), 2);
     }
     #[test]
@@ -357,7 +1188,12 @@ mod tests {
         let componet = Component::TextComponent(TextComponent::new(TextNode::Paragraph, text));
         let root = ComponentRoot::new(None, vec![componet]);
         let query = "hello world";
-        let result = find_with_ref(query, root.words());
+        let result = find_with_ref(
+            query,
+            root.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result.len(), 3);
     }
 
@@ -374,7 +1210,12 @@ mod tests {
         let componet = Component::TextComponent(TextComponent::new(TextNode::Paragraph, text));
         let root = ComponentRoot::new(None, vec![componet]);
         let query = "hello world";
-        let result = find_with_ref(query, root.words());
+        let result = find_with_ref(
+            query,
+            root.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
 
         assert_ne!(result[0], root.words()[0]);
         assert_eq!(result[0], root.words()[1]);
@@ -382,6 +1223,27 @@ mod tests {
         assert_eq!(result[2], root.words()[3]);
     }
 
+    #[test]
+    fn test_word_by_ref_regex() {
+        let text = vec![
+            Word::new("Hello".to_string(), WordType::Bold),
+            Word::new(" ".to_string(), WordType::White),
+            Word::new("world".to_string(), WordType::Normal),
+        ];
+
+        let componet = Component::TextComponent(TextComponent::new(TextNode::Paragraph, text));
+        let root = ComponentRoot::new(None, vec![componet]);
+        let query = r"^Hello";
+        let result = find_with_ref(
+            query,
+            root.words(),
+            SearchMode::Regex,
+            MatchOptions::default(),
+        );
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].content(), "Hello");
+    }
+
     #[test]
     fn test_long_match() {
         let text = "`MD-TUI` is a TUI application for viewing markdown files directly in your
@@ -393,14 +1255,97 @@ your markdown notes, or opening external links from someones README.
 
         let markdown = parse_markdown(None, text, 80);
 
-        let result = find_with_ref("in", markdown.words());
+        let result = find_with_ref(
+            "in",
+            markdown.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         dbg!(&result);
         assert_eq!(result.len(), 2);
 
-        let result = find_with_ref("markdown notes,", markdown.words());
+        let result = find_with_ref(
+            "markdown notes,",
+            markdown.words(),
+            SearchMode::Fuzzy { precision: 0 },
+            MatchOptions::default(),
+        );
         assert_eq!(result.len(), 3);
     }
 
+    #[test]
+    fn test_build_outline_collects_headings_in_order() {
+        let text = "# Title\n\nSome text.\n\n## Sub Heading\n\nMore text.\n";
+        let markdown = parse_markdown(None, text, 80);
+        let outline = build_outline(&markdown);
+
+        assert_eq!(outline.len(), 2);
+        assert_eq!(outline[0].level, 1);
+        assert_eq!(outline[0].slug, "title");
+        assert_eq!(outline[1].level, 2);
+        assert_eq!(outline[1].slug, "sub-heading");
+    }
+
+    #[test]
+    fn test_previous_and_next_heading() {
+        let text = "# One\n\nbody\n\n# Two\n\nbody\n\n# Three\n";
+        let markdown = parse_markdown(None, text, 80);
+        let outline = build_outline(&markdown);
+
+        let middle = outline[1].component_index;
+        assert_eq!(previous_heading(&outline, middle).unwrap().slug, "two");
+        assert_eq!(next_heading(&outline, middle).unwrap().slug, "three");
+    }
+
+    #[test]
+    fn test_resolve_heading_link() {
+        let text = "# My Heading\n";
+        let markdown = parse_markdown(None, text, 80);
+        let outline = build_outline(&markdown);
+
+        assert_eq!(
+            resolve_heading_link(&outline, "my-heading").unwrap().level,
+            1
+        );
+        assert!(resolve_heading_link(&outline, "missing").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_score_rejects_non_subsequence() {
+        assert_eq!(fuzzy_score("notes.md", "zx"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranks_word_start_and_consecutive_matches_higher() {
+        // "ab" appears contiguously right after a path separator in
+        // "src/abfile.md", but only as a scattered, gapped match in
+        // "a1b2c3.md", so the former should score higher.
+        let word_start = fuzzy_score("src/abfile.md", "ab").unwrap();
+        let scattered = fuzzy_score("a1b2c3.md", "ab").unwrap();
+        assert!(word_start > scattered);
+    }
+
+    #[test]
+    fn test_fuzzy_score_finds_best_alignment_not_just_leftmost() {
+        // The earliest 'a' (index 1) is not a word start and is far from
+        // the nearest 'b' (index 7), while a later 'a' (index 9) is a word
+        // start immediately followed by a 'b' (index 10). A leftmost-match
+        // scorer would be stuck with the first, far worse, pairing (score
+        // 17); the best alignment uses the second pairing (score 64).
+        let text = "zaxxxxxb_ab";
+        assert_eq!(fuzzy_score(text, "ab"), Some(64));
+    }
+
+    #[test]
+    fn test_find_files_orders_by_score() {
+        let files = vec![
+            MdFile::new("a1b2c3.md".to_string(), "a1b2c3.md".to_string()),
+            MdFile::new("src/abfile.md".to_string(), "abfile.md".to_string()),
+        ];
+        let result = find_files(&files, "ab");
+        assert_eq!(result[0].path, "src/abfile.md");
+    }
+
     #[test]
     fn test_alphanumeric() {
         let s = "#Hello, world!";